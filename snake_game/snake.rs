@@ -23,47 +23,208 @@ enum Direction {
     Right,
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Walls,
+    Wrap,
+}
+
+// A parsed level: static obstacles plus optional snake-start and food
+// overrides. An empty level is the default open arena.
+#[derive(Clone)]
+struct Level {
+    walls: Vec<Position>,
+    start: Position,
+    food: Option<Position>,
+}
+
+impl Level {
+    fn default() -> Self {
+        Level {
+            walls: Vec::new(),
+            start: Position { x: 5, y: 5 },
+            food: None,
+        }
+    }
+
+    // Parse a level file: one char per cell, rows separated by newlines.
+    // `#` is a wall, `.` empty, `O` the snake start, `*` the food.
+    fn load(path: &str) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut level = Level {
+            walls: Vec::new(),
+            start: Position { x: 5, y: 5 },
+            food: None,
+        };
+        for (y, line) in text.lines().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                let pos = Position { x: x as u16, y: y as u16 };
+                if pos.x >= WIDTH || pos.y >= HEIGHT {
+                    continue;
+                }
+                match ch {
+                    '#' => level.walls.push(pos),
+                    'O' => level.start = pos,
+                    '*' => level.food = Some(pos),
+                    _ => {}
+                }
+            }
+        }
+        Ok(level)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CellState {
+    Empty,
+    Snake,
+    Food,
+    Wall,
+}
+
+impl CellState {
+    fn color(self) -> Color {
+        match self {
+            CellState::Empty => Color::Reset,
+            CellState::Snake => Color::Green,
+            CellState::Food => Color::Red,
+            CellState::Wall => Color::DarkGrey,
+        }
+    }
+}
+
+// A single drawable board cell that knows how to paint itself at its
+// coordinates. The renderer only builds these for cells that changed.
+struct Cell {
+    state: CellState,
+    x: u16,
+    y: u16,
+}
+
+impl Cell {
+    fn render(&self, stdout: &mut std::io::Stdout) {
+        queue!(
+            stdout,
+            cursor::MoveTo(self.x, self.y),
+            SetBackgroundColor(self.state.color()),
+            Print(" "),
+            SetBackgroundColor(Color::Reset)
+        )
+        .unwrap();
+    }
+}
+
+// Wrap a coordinate back inside the 1-cell border of a dimension of size `dim`.
+fn wrap(coord: u16, dim: u16) -> u16 {
+    if coord == 0 {
+        dim - 2
+    } else if coord >= dim - 1 {
+        1
+    } else {
+        coord
+    }
+}
+
+fn is_opposite(a: Direction, b: Direction) -> bool {
+    matches!(
+        (a, b),
+        (Direction::Up, Direction::Down)
+            | (Direction::Down, Direction::Up)
+            | (Direction::Left, Direction::Right)
+            | (Direction::Right, Direction::Left)
+    )
+}
+
 #[derive(Clone, Copy, PartialEq)]
 struct Position {
     x: u16,
     y: u16,
 }
 
+// At most this many turns are buffered; extra taps within a frame are dropped.
+const INPUT_QUEUE_CAP: usize = 3;
+
 struct Game {
     snake: VecDeque<Position>,
     direction: Direction,
+    inputs: VecDeque<Direction>,
     food: Position,
     game_over: bool,
+    mode: Mode,
+    level: Level,
+    score: u32,
+    // Cell states as last drawn, so `draw` can emit only the diff.
+    frame: Vec<CellState>,
 }
 
 impl Game {
-    fn new() -> Self {
+    fn new(mode: Mode, level: Level) -> Self {
         let mut snake = VecDeque::new();
-        snake.push_back(Position { x: 5, y: 5 });
+        snake.push_back(level.start);
 
-        let food = Game::generate_food(&snake);
+        let food = level
+            .food
+            .unwrap_or_else(|| Game::generate_food(&snake, &level.walls));
         Game {
             snake,
             direction: Direction::Right,
+            inputs: VecDeque::new(),
             food,
             game_over: false,
+            mode,
+            level,
+            score: 0,
+            frame: vec![CellState::Empty; (WIDTH * HEIGHT) as usize],
         }
     }
 
-    fn generate_food(snake: &VecDeque<Position>) -> Position {
+    // Re-initialise play state in place, reusing the same terminal session.
+    fn reset(&mut self) {
+        *self = Game::new(self.mode, self.level.clone());
+    }
+
+    // Frame interval shortens as the score climbs, clamped to a floor so the
+    // game stays playable.
+    fn frame_interval(&self) -> Duration {
+        let ms = 120u64.saturating_sub(self.score as u64 * 5).max(50);
+        Duration::from_millis(ms)
+    }
+
+    fn generate_food(snake: &VecDeque<Position>, walls: &[Position]) -> Position {
         let mut rng = rand::thread_rng();
-        loop {
+        // Bound the retries so a nearly-full board fails gracefully instead of
+        // spinning forever; fall back to the first free interior cell.
+        for _ in 0..1000 {
             let pos = Position {
                 x: rng.gen_range(1..WIDTH - 1),
                 y: rng.gen_range(1..HEIGHT - 1),
             };
-            if !snake.contains(&pos) {
+            if !snake.contains(&pos) && !walls.contains(&pos) {
                 return pos;
             }
         }
+        for y in 1..HEIGHT - 1 {
+            for x in 1..WIDTH - 1 {
+                let pos = Position { x, y };
+                if !snake.contains(&pos) && !walls.contains(&pos) {
+                    return pos;
+                }
+            }
+        }
+        // Board is completely full; place food on the head as a last resort.
+        *snake.front().unwrap()
     }
 
     fn update(&mut self) {
+        // Consume at most one buffered turn per tick, validated against the
+        // last *applied* direction so a flurry of taps can't fold the snake
+        // back on itself.
+        if let Some(next) = self.inputs.pop_front() {
+            if !is_opposite(self.direction, next) {
+                self.direction = next;
+            }
+        }
+
         let head = *self.snake.front().unwrap();
         let new_head = match self.direction {
             Direction::Up => Position { x: head.x, y: head.y.saturating_sub(1) },
@@ -72,8 +233,26 @@ impl Game {
             Direction::Right => Position { x: head.x + 1, y: head.y },
         };
 
-        // Check for collisions
-        if new_head.x == 0 || new_head.y == 0 || new_head.x >= WIDTH || new_head.y >= HEIGHT || self.snake.contains(&new_head) {
+        let new_head = match self.mode {
+            // Walls: hitting the border ends the game.
+            Mode::Walls => {
+                if new_head.x == 0 || new_head.y == 0 || new_head.x >= WIDTH || new_head.y >= HEIGHT {
+                    self.game_over = true;
+                    return;
+                }
+                new_head
+            }
+            // Wrap: stepping off one edge re-enters from the opposite one,
+            // staying inside the 1-cell border.
+            Mode::Wrap => Position {
+                x: wrap(new_head.x, WIDTH),
+                y: wrap(new_head.y, HEIGHT),
+            },
+        };
+
+        // Self-collision — or walking into an obstacle — ends the game in
+        // either mode.
+        if self.snake.contains(&new_head) || self.level.walls.contains(&new_head) {
             self.game_over = true;
             return;
         }
@@ -81,41 +260,46 @@ impl Game {
         self.snake.push_front(new_head);
 
         if new_head == self.food {
-            self.food = Game::generate_food(&self.snake);
+            self.score += 1;
+            self.food = Game::generate_food(&self.snake, &self.level.walls);
         } else {
             self.snake.pop_back();
         }
     }
 
-    fn change_direction(&mut self, new_direction: Direction) {
-        // Prevent 180-degree turns
-        match (self.direction, new_direction) {
-            (Direction::Up, Direction::Down) => {}
-            (Direction::Down, Direction::Up) => {}
-            (Direction::Left, Direction::Right) => {}
-            (Direction::Right, Direction::Left) => {}
-            _ => self.direction = new_direction,
+    fn queue_direction(&mut self, new_direction: Direction) {
+        // Buffer the turn for the next tick; drop taps once the queue is full.
+        if self.inputs.len() < INPUT_QUEUE_CAP {
+            self.inputs.push_back(new_direction);
         }
     }
 
-    fn draw(&self, stdout: &mut std::io::Stdout) {
-        queue!(stdout, cursor::MoveTo(0, 0), Clear(ClearType::All)).unwrap();
+    fn draw(&mut self, stdout: &mut std::io::Stdout) {
+        // Compute the cell states for this frame, then repaint only the cells
+        // whose state differs from the previously drawn frame.
+        let mut next = vec![CellState::Empty; self.frame.len()];
+        for pos in &self.level.walls {
+            next[(pos.y * WIDTH + pos.x) as usize] = CellState::Wall;
+        }
+        for pos in &self.snake {
+            next[(pos.y * WIDTH + pos.x) as usize] = CellState::Snake;
+        }
+        next[(self.food.y * WIDTH + self.food.x) as usize] = CellState::Food;
+
         for y in 0..HEIGHT {
             for x in 0..WIDTH {
-                let pos = Position { x, y };
-                if self.snake.contains(&pos) {
-                    queue!(stdout, SetBackgroundColor(Color::Green), Print(" "), SetBackgroundColor(Color::Reset)).unwrap();
-                } else if pos == self.food {
-                    queue!(stdout, SetBackgroundColor(Color::Red), Print(" "), SetBackgroundColor(Color::Reset)).unwrap();
-                } else {
-                    queue!(stdout, Print(" ")).unwrap();
+                let idx = (y * WIDTH + x) as usize;
+                if next[idx] != self.frame[idx] {
+                    Cell { state: next[idx], x, y }.render(stdout);
                 }
             }
-            queue!(stdout, Print("\r\n")).unwrap();
         }
+        self.frame = next;
+
+        queue!(stdout, cursor::MoveTo(0, HEIGHT), Print(format!("Score: {}", self.score))).unwrap();
 
         if self.game_over {
-            queue!(stdout, Print("Game Over! Press 'q' to exit.\n")).unwrap();
+            queue!(stdout, cursor::MoveTo(0, HEIGHT + 1), Print("Game Over! Press 'r' to restart or 'q' to quit.")).unwrap();
         }
 
         stdout.flush().unwrap();
@@ -123,40 +307,64 @@ impl Game {
 }
 
 fn main() {
+    // CLI: `wrap` selects the toroidal board and `walls` the bordered one;
+    // any other argument is treated as a path to a level file to load.
+    let mut mode = Mode::Walls;
+    let mut level = Level::default();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "wrap" => mode = Mode::Wrap,
+            "walls" => mode = Mode::Walls,
+            path => match Level::load(path) {
+                Ok(loaded) => level = loaded,
+                Err(e) => {
+                    eprintln!("failed to load level '{}': {}", path, e);
+                    return;
+                }
+            },
+        }
+    }
+
     let mut stdout = stdout();
     terminal::enable_raw_mode().unwrap();
-    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide).unwrap();
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide, Clear(ClearType::All)).unwrap();
 
-    let mut game = Game::new();
+    let mut game = Game::new(mode, level);
     let mut last_frame = Instant::now();
+    let mut quit = false;
 
     loop {
         while event::poll(Duration::from_millis(0)).unwrap() {
             if let Event::Key(KeyEvent { code, .. }) = event::read().unwrap() {
                 match code {
-                    KeyCode::Up => game.change_direction(Direction::Up),
-                    KeyCode::Down => game.change_direction(Direction::Down),
-                    KeyCode::Left => game.change_direction(Direction::Left),
-                    KeyCode::Right => game.change_direction(Direction::Right),
+                    KeyCode::Up => game.queue_direction(Direction::Up),
+                    KeyCode::Down => game.queue_direction(Direction::Down),
+                    KeyCode::Left => game.queue_direction(Direction::Left),
+                    KeyCode::Right => game.queue_direction(Direction::Right),
+                    KeyCode::Char('q') if game.game_over => {
+                        quit = true;
+                    }
+                    KeyCode::Char('r') if game.game_over => {
+                        game.reset();
+                        execute!(stdout, Clear(ClearType::All)).unwrap();
+                        last_frame = Instant::now();
+                    }
                     KeyCode::Char('q') => {
-                        game.game_over = true;
-                        break;
+                        quit = true;
                     }
                     _ => {}
                 }
             }
         }
 
-        if last_frame.elapsed() >= Duration::from_millis(120) {
-            if !game.game_over {
-                game.update();
-            }
-            game.draw(&mut stdout);
-            last_frame = Instant::now();
+        if quit {
+            break;
         }
 
-        if game.game_over {
-            break;
+        if !game.game_over && last_frame.elapsed() >= game.frame_interval() {
+            game.update();
+            game.draw(&mut stdout);
+            last_frame = Instant::now();
         }
 
         sleep(Duration::from_millis(10));